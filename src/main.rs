@@ -1,5 +1,7 @@
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct JsonifyValue {
@@ -9,13 +11,279 @@ pub struct JsonifyValue {
 
 pub struct Jsonify {
     values: HashSet<(String, JsonifyValue)>,
+    /// Present once the document was opened via [`Jsonify::open`]; mirrors
+    /// every write into the embedded database so the document survives
+    /// process restarts. Absent (and dependency-free) without the
+    /// `sled-store` feature.
+    #[cfg(feature = "sled-store")]
+    store: Option<sled::Db>,
+}
+
+/// Errors that can occur while reading or reconstructing a document from the
+/// flattened key/value store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonifyError {
+    /// A path segment was used both as a container (object/array) and as a
+    /// scalar leaf, e.g. `address` and `address.city` stored at the same time.
+    PathConflict(String),
+    /// A typed accessor was called for a key that isn't in the store.
+    KeyNotFound(String),
+    /// A typed accessor was called for a key whose stored type doesn't
+    /// match the requested type.
+    TypeMismatch {
+        key: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for JsonifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonifyError::PathConflict(path) => {
+                write!(f, "conflicting path '{}': used as both a container and a scalar value", path)
+            }
+            JsonifyError::KeyNotFound(key) => write!(f, "key '{}' not found", key),
+            JsonifyError::TypeMismatch { key, expected, found } => {
+                write!(f, "expected {} at key '{}', found {}", expected, key, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonifyError {}
+
+/// A single segment of a dotted/bracket key path, e.g. `address.cities[0]`
+/// tokenizes to `[Key("address"), Key("cities"), Index(0)]`.
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// The scalar flavor of a single leaf, used by `to_rust_structs` to infer
+/// field types. Kept separate from `value_type` so integral and
+/// floating-point numbers can be told apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LeafKind {
+    Str,
+    Bool,
+    Int,
+    Float,
+    Null,
+    Unknown,
+}
+
+/// The inferred shape of a field (or the whole document) used to generate
+/// Rust struct definitions.
+#[derive(Debug, Clone)]
+enum FieldShape {
+    Scalar(String),
+    Nullable(Box<FieldShape>),
+    Array(Box<FieldShape>),
+    Struct(BTreeMap<String, FieldShape>),
+}
+
+/// Escapes literal `\`, `.`, `[` and `]` in a single JSON object key so it
+/// can be embedded as one segment of a dotted/bracket flattened path
+/// without being mistaken for a path separator on reconstruction (e.g. an
+/// object key literally named `"a.b"` flattens to the single segment
+/// `a\.b`, not two segments `a` and `b`). `tokenize_path` reverses this.
+fn escape_key_segment(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '\\' | '.' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Tokenizes a flattened key like `address.cities[0]` into path segments,
+/// unescaping `\.`, `\[`, `\]` and `\\` back into their literal characters.
+/// Shared by `Jsonify` and `LazyJsonify`, since both rebuild nested
+/// documents from the same dotted/bracket key scheme.
+fn tokenize_path(key: &str) -> Vec<PathSegment> {
+    tokenize_path_with_offsets(key)
+        .into_iter()
+        .map(|(segment, _)| segment)
+        .collect()
+}
+
+/// Like `tokenize_path`, but also returns, alongside each segment, the byte
+/// offset in `key` of the end of that segment's literal representation --
+/// i.e. the length of the prefix of `key` (escapes and all) that addresses
+/// the value the segment names, which is the container the following
+/// segment lives in. `insert_path` uses this to check
+/// whether that exact prefix is itself a separately stored leaf, rather
+/// than just an unfilled placeholder it's free to grow into.
+fn tokenize_path_with_offsets(key: &str) -> Vec<(PathSegment, usize)> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = key.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(&(_, next)) = chars.peek() {
+                    current.push(next);
+                    chars.next();
+                }
+            }
+            '.' => {
+                if !current.is_empty() {
+                    segments.push((PathSegment::Key(std::mem::take(&mut current)), idx));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push((PathSegment::Key(std::mem::take(&mut current)), idx));
+                }
+                let mut index_str = String::new();
+                let mut end = idx + c.len_utf8();
+                for (idx2, c2) in chars.by_ref() {
+                    end = idx2 + c2.len_utf8();
+                    if c2 == ']' {
+                        break;
+                    }
+                    index_str.push(c2);
+                }
+                match index_str.parse::<usize>() {
+                    Ok(index) => segments.push((PathSegment::Index(index), end)),
+                    Err(_) => segments.push((PathSegment::Key(format!("[{}]", index_str)), end)),
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        segments.push((PathSegment::Key(current), key.len()));
+    }
+
+    segments
+}
+
+/// Inserts `leaf` at `segments` within `root`, growing objects/arrays as
+/// needed. `full_path` is the complete flattened key `segments` was
+/// tokenized from (used for error reporting and, together with each
+/// segment's offset, to look up `consumed` in `leaf_paths`). `leaf_paths`
+/// is the full set of flattened keys being reconstructed; an explicit
+/// `null` leaf and an unfilled container placeholder are both represented
+/// by `Value::Null`, so `root.is_null()` alone can't tell a real conflict
+/// (growing into a path already stored as its own leaf) from an ordinary
+/// first visit -- `leaf_paths` disambiguates them.
+fn insert_path(
+    root: &mut Value,
+    segments: &[(PathSegment, usize)],
+    leaf: Value,
+    full_path: &str,
+    consumed: usize,
+    leaf_paths: &HashSet<&str>,
+) -> Result<(), JsonifyError> {
+    match segments.split_first() {
+        None => {
+            *root = leaf;
+            Ok(())
+        }
+        Some(((PathSegment::Key(key), end), rest)) => {
+            if root.is_null() {
+                let current_path = &full_path[..consumed];
+                if !current_path.is_empty() && leaf_paths.contains(current_path) {
+                    return Err(JsonifyError::PathConflict(full_path.to_string()));
+                }
+                *root = Value::Object(serde_json::Map::new());
+            }
+            let object = root
+                .as_object_mut()
+                .ok_or_else(|| JsonifyError::PathConflict(full_path.to_string()))?;
+            let entry = object.entry(key.clone()).or_insert(Value::Null);
+            insert_path(entry, rest, leaf, full_path, *end, leaf_paths)
+        }
+        Some(((PathSegment::Index(index), end), rest)) => {
+            if root.is_null() {
+                let current_path = &full_path[..consumed];
+                if !current_path.is_empty() && leaf_paths.contains(current_path) {
+                    return Err(JsonifyError::PathConflict(full_path.to_string()));
+                }
+                *root = Value::Array(Vec::new());
+            }
+            let array = root
+                .as_array_mut()
+                .ok_or_else(|| JsonifyError::PathConflict(full_path.to_string()))?;
+            if array.len() <= *index {
+                array.resize(*index + 1, Value::Null);
+            }
+            insert_path(&mut array[*index], rest, leaf, full_path, *end, leaf_paths)
+        }
+    }
 }
 
 impl Jsonify {
     pub fn new(json: &str) -> Self {
         let mut values: HashSet<(String, JsonifyValue)> = HashSet::new();
         Self::parse_json(json, String::new(), &mut values);
-        Jsonify { values }
+        Jsonify {
+            values,
+            #[cfg(feature = "sled-store")]
+            store: None,
+        }
+    }
+
+    /// Opens (creating if needed) a `sled` database directory at `path` and
+    /// loads any previously persisted entries into the returned `Jsonify`.
+    /// Every subsequent mutation (`add_to_json`, `replace`,
+    /// `remove_from_json`, `merge_json`) is written through to the store.
+    #[cfg(feature = "sled-store")]
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> sled::Result<Self> {
+        let db = sled::open(path)?;
+        let mut values = HashSet::new();
+
+        for item in db.iter() {
+            let (key_bytes, value_bytes) = item?;
+            let key = String::from_utf8_lossy(&key_bytes).into_owned();
+            if let Some(value) = Self::decode_stored_value(&value_bytes) {
+                values.insert((key, value));
+            }
+        }
+
+        Ok(Jsonify {
+            values,
+            store: Some(db),
+        })
+    }
+
+    #[cfg(feature = "sled-store")]
+    fn encode_stored_value(value: &JsonifyValue) -> Vec<u8> {
+        let encoded = serde_json::json!({
+            "value": value.value,
+            "value_type": value.value_type,
+        });
+        serde_json::to_vec(&encoded).unwrap_or_default()
+    }
+
+    #[cfg(feature = "sled-store")]
+    fn decode_stored_value(bytes: &[u8]) -> Option<JsonifyValue> {
+        let encoded: Value = serde_json::from_slice(bytes).ok()?;
+        Some(JsonifyValue {
+            value: encoded.get("value")?.clone(),
+            value_type: encoded.get("value_type")?.as_str()?.to_string(),
+        })
+    }
+
+    #[cfg(feature = "sled-store")]
+    fn persist_set(&self, key: &str, value: &JsonifyValue) {
+        if let Some(db) = &self.store {
+            let _ = db.insert(key.as_bytes(), Self::encode_stored_value(value));
+        }
+    }
+
+    #[cfg(feature = "sled-store")]
+    fn persist_remove(&self, key: &str) {
+        if let Some(db) = &self.store {
+            let _ = db.remove(key.as_bytes());
+        }
     }
 
     fn parse_json(json: &str, prefix: String, hashset: &mut HashSet<(String, JsonifyValue)>) {
@@ -24,10 +292,11 @@ impl Jsonify {
         match json {
             Value::Object(map) => {
                 for (key, value) in map {
+                    let escaped_key = escape_key_segment(&key);
                     let new_prefix = if prefix.is_empty() {
-                        key
+                        escaped_key
                     } else {
-                        format!("{}.{}", prefix, key)
+                        format!("{}.{}", prefix, escaped_key)
                     };
                     Self::parse_json(&value.to_string(), new_prefix, hashset);
                 }
@@ -79,6 +348,8 @@ impl Jsonify {
                     _ => "Unknown".to_string(),
                 },
             };
+            #[cfg(feature = "sled-store")]
+            self.persist_set(key, &new_value_entry);
             self.values.insert((key.to_string(), new_value_entry));
             true
         } else {
@@ -86,14 +357,31 @@ impl Jsonify {
         }
     }
 
-    pub fn to_json(&self) -> String {
-        let mut json_map: serde_json::Map<String, Value> = serde_json::Map::new();
+    /// Rebuilds the nested document represented by the flattened key store
+    /// and serializes it back to a JSON string. Dotted segments become
+    /// object members and bracketed segments become array elements (growing
+    /// and null-padding arrays to the referenced index). Returns
+    /// `JsonifyError::PathConflict` if a path is used as both a container
+    /// and a scalar.
+    pub fn to_json(&self) -> Result<String, JsonifyError> {
+        let mut root = Value::Null;
 
-        for (key, value) in &self.values {
-            json_map.insert(key.clone(), value.value.clone());
+        // HashSet has no deterministic order; sort so array indices and
+        // object members are rebuilt consistently across calls.
+        let mut entries: Vec<&(String, JsonifyValue)> = self.values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let leaf_paths: HashSet<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        for (key, value) in entries {
+            let segments = tokenize_path_with_offsets(key);
+            insert_path(&mut root, &segments, value.value.clone(), key, 0, &leaf_paths)?;
+        }
+
+        if root.is_null() {
+            root = Value::Object(serde_json::Map::new());
         }
 
-        serde_json::to_string(&json_map).unwrap_or_else(|_| "{}".to_string())
+        Ok(serde_json::to_string(&root).unwrap_or_else(|_| "{}".to_string()))
     }
 
     pub fn add_to_json(&mut self, key: &str, value: Value) {
@@ -107,12 +395,16 @@ impl Jsonify {
                 _ => "Unknown".to_string(),
             },
         };
+        #[cfg(feature = "sled-store")]
+        self.persist_set(key, &new_value);
         self.values.insert((key.to_string(), new_value));
     }
 
     pub fn remove_from_json(&mut self, key: &str) -> bool {
         if let Some(entry) = self.values.iter().find(|(k, _)| k == key).cloned() {
             self.values.remove(&entry);
+            #[cfg(feature = "sled-store")]
+            self.persist_remove(key);
             true
         } else {
             false
@@ -131,12 +423,835 @@ impl Jsonify {
         let mut new_values: HashSet<(String, JsonifyValue)> = HashSet::new();
         Self::parse_json(other_json, String::new(), &mut new_values);
         for (key, value) in new_values {
+            #[cfg(feature = "sled-store")]
+            self.persist_set(&key, &value);
             self.values.insert((key, value));
         }
     }
 
+    /// Applies an RFC 7396 JSON Merge Patch to the document. Object members
+    /// set to `null` delete the corresponding key (and every descendant key
+    /// sharing its dotted/bracket prefix); other object members are merged
+    /// recursively; a non-object patch value replaces the entire target at
+    /// that path.
+    pub fn merge_patch(&mut self, patch: &str) {
+        let patch_value: Value = serde_json::from_str(patch).unwrap_or(Value::Null);
+        if patch_value.is_object() {
+            // Same as for nested members: a scalar or array root is replaced,
+            // not merged alongside, by an object patch.
+            self.remove_non_object_leaf_persisted("");
+        }
+        self.apply_merge_patch(String::new(), &patch_value);
+    }
+
+    fn apply_merge_patch(&mut self, prefix: String, patch: &Value) {
+        let Value::Object(map) = patch else {
+            // A non-object patch replaces the entire target at `prefix`,
+            // including the document root (`prefix` empty).
+            self.remove_prefix_persisted(&prefix);
+            self.insert_parsed(&patch.to_string(), prefix);
+            return;
+        };
+
+        for (key, value) in map {
+            let escaped_key = escape_key_segment(key);
+            let new_prefix = if prefix.is_empty() {
+                escaped_key
+            } else {
+                format!("{}.{}", prefix, escaped_key)
+            };
+
+            match value {
+                Value::Null => self.remove_prefix_persisted(&new_prefix),
+                Value::Object(_) => {
+                    // The target may currently be a scalar or an array; merging an
+                    // object patch into it only touches `new_prefix.*` descendants,
+                    // so the old scalar/array leaves must be cleared first or they
+                    // survive as stale, un-mergeable entries alongside the merge.
+                    self.remove_non_object_leaf_persisted(&new_prefix);
+                    self.apply_merge_patch(new_prefix, value);
+                }
+                _ => {
+                    self.remove_prefix_persisted(&new_prefix);
+                    self.insert_parsed(&value.to_string(), new_prefix);
+                }
+            }
+        }
+    }
+
+    /// Parses `json` and inserts every resulting leaf under `prefix`,
+    /// writing each one through to the backing store if present.
+    fn insert_parsed(&mut self, json: &str, prefix: String) {
+        let mut new_values = HashSet::new();
+        Self::parse_json(json, prefix, &mut new_values);
+        for (key, value) in new_values {
+            #[cfg(feature = "sled-store")]
+            self.persist_set(&key, &value);
+            self.values.insert((key, value));
+        }
+    }
+
+    /// Removes `prefix` as a scalar leaf and any array elements nested
+    /// directly under it (`prefix[0]`, ...), but leaves `prefix.foo`
+    /// object members untouched so an object patch can merge into them.
+    fn remove_non_object_leaf_persisted(&mut self, prefix: &str) {
+        let bracket_prefix = format!("{}[", prefix);
+        let removed: Vec<String> = self
+            .values
+            .iter()
+            .filter(|(k, _)| k == prefix || k.starts_with(&bracket_prefix))
+            .map(|(k, _)| k.clone())
+            .collect();
+        self.values
+            .retain(|(k, _)| k != prefix && !k.starts_with(&bracket_prefix));
+        #[cfg(feature = "sled-store")]
+        for key in &removed {
+            self.persist_remove(key);
+        }
+        #[cfg(not(feature = "sled-store"))]
+        let _ = removed;
+    }
+
+    /// Reads the scalar stored at `path`. Equivalent to `get_value`, kept
+    /// under the path-addressable naming for symmetry with `set_by_path`
+    /// and `remove_by_path`.
+    pub fn get_by_path(&self, path: &str) -> Option<Value> {
+        self.get_value(path)
+    }
+
+    /// Sets the value at `path`, creating the key if it doesn't exist yet.
+    /// If `value` is an object or array it is flattened under `path` just
+    /// like the rest of the document, replacing whatever was previously
+    /// stored there (and its descendants).
+    pub fn set_by_path(&mut self, path: &str, value: Value) {
+        self.remove_prefix_persisted(path);
+        let mut new_values = HashSet::new();
+        Self::parse_json(&value.to_string(), path.to_string(), &mut new_values);
+        for (key, value) in new_values {
+            #[cfg(feature = "sled-store")]
+            self.persist_set(&key, &value);
+            self.values.insert((key, value));
+        }
+    }
+
+    /// Removes `path` and every descendant key nested under it. Returns
+    /// `true` if anything was removed.
+    pub fn remove_by_path(&mut self, path: &str) -> bool {
+        let before = self.values.len();
+        self.remove_prefix_persisted(path);
+        self.values.len() != before
+    }
+
+    /// Removes every key nested under `prefix` from both `self.values` and,
+    /// when present, the backing `sled` store.
+    fn remove_prefix_persisted(&mut self, prefix: &str) {
+        // An empty prefix addresses the document root, under which every
+        // top-level key (`a`, `a.b`, `a[0]`, ...) is nested -- not just the
+        // ones that happen to start with the literal "." or "[" produced by
+        // formatting a non-empty prefix.
+        let removed: Vec<String> = if prefix.is_empty() {
+            self.values.iter().map(|(k, _)| k.clone()).collect()
+        } else {
+            let dot_prefix = format!("{}.", prefix);
+            let bracket_prefix = format!("{}[", prefix);
+            self.values
+                .iter()
+                .filter(|(k, _)| {
+                    k == prefix || k.starts_with(&dot_prefix) || k.starts_with(&bracket_prefix)
+                })
+                .map(|(k, _)| k.clone())
+                .collect()
+        };
+        if prefix.is_empty() {
+            self.values.clear();
+        } else {
+            let dot_prefix = format!("{}.", prefix);
+            let bracket_prefix = format!("{}[", prefix);
+            self.values.retain(|(k, _)| {
+                k != prefix && !k.starts_with(&dot_prefix) && !k.starts_with(&bracket_prefix)
+            });
+        }
+        #[cfg(feature = "sled-store")]
+        for key in &removed {
+            self.persist_remove(key);
+        }
+        #[cfg(not(feature = "sled-store"))]
+        let _ = removed;
+    }
+
+    /// Returns a new `Jsonify` containing only the entries nested under
+    /// `prefix`, re-rooted so that `prefix` becomes the empty path (e.g.
+    /// `address.city` under prefix `address` becomes `city`).
+    pub fn get_subtree(&self, prefix: &str) -> Jsonify {
+        let dot_prefix = format!("{}.", prefix);
+        let mut values = HashSet::new();
+
+        for (key, value) in &self.values {
+            if key == prefix {
+                values.insert((String::new(), value.clone()));
+            } else if let Some(rest) = key.strip_prefix(&dot_prefix) {
+                values.insert((rest.to_string(), value.clone()));
+            } else if let Some(rest) = key.strip_prefix(prefix) {
+                if rest.starts_with('[') {
+                    values.insert((rest.to_string(), value.clone()));
+                }
+            }
+        }
+
+        Jsonify {
+            values,
+            #[cfg(feature = "sled-store")]
+            store: None,
+        }
+    }
+
+}
+
+/// Typed, fallible accessors for a [`Jsonify`] document. Each method checks
+/// the type of the entry's stored value and returns a descriptive
+/// `JsonifyError` instead of an `Option<Value>` the caller has to match on.
+pub trait TypedAccess {
+    fn get_str(&self, key: &str) -> Result<&str, JsonifyError>;
+    fn get_bool(&self, key: &str) -> Result<bool, JsonifyError>;
+    fn get_i64(&self, key: &str) -> Result<i64, JsonifyError>;
+    fn get_u64(&self, key: &str) -> Result<u64, JsonifyError>;
+    fn get_f64(&self, key: &str) -> Result<f64, JsonifyError>;
+}
+
+impl TypedAccess for Jsonify {
+    fn get_str(&self, key: &str) -> Result<&str, JsonifyError> {
+        let value = Self::find_typed_entry(self, key)?;
+        value.as_str().ok_or_else(|| Self::type_mismatch(key, "String", value))
+    }
+
+    fn get_bool(&self, key: &str) -> Result<bool, JsonifyError> {
+        let value = Self::find_typed_entry(self, key)?;
+        value.as_bool().ok_or_else(|| Self::type_mismatch(key, "Bool", value))
+    }
+
+    fn get_i64(&self, key: &str) -> Result<i64, JsonifyError> {
+        let value = Self::find_typed_entry(self, key)?;
+        value.as_i64().ok_or_else(|| Self::numeric_mismatch(key, "i64", value))
+    }
+
+    fn get_u64(&self, key: &str) -> Result<u64, JsonifyError> {
+        let value = Self::find_typed_entry(self, key)?;
+        value.as_u64().ok_or_else(|| Self::numeric_mismatch(key, "u64", value))
+    }
+
+    fn get_f64(&self, key: &str) -> Result<f64, JsonifyError> {
+        let value = Self::find_typed_entry(self, key)?;
+        value.as_f64().ok_or_else(|| Self::type_mismatch(key, "Number", value))
+    }
+}
+
+impl Jsonify {
+    fn find_typed_entry(&self, key: &str) -> Result<&Value, JsonifyError> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| &v.value)
+            .ok_or_else(|| JsonifyError::KeyNotFound(key.to_string()))
+    }
+
+    fn type_mismatch(key: &str, expected: &str, found: &Value) -> JsonifyError {
+        let found_type = match found {
+            Value::String(_) => "String",
+            Value::Number(_) => "Number",
+            Value::Bool(_) => "Bool",
+            Value::Null => "Null",
+            _ => "Unknown",
+        };
+        JsonifyError::TypeMismatch {
+            key: key.to_string(),
+            expected: expected.to_string(),
+            found: found_type.to_string(),
+        }
+    }
+
+    /// Like `type_mismatch`, but for `get_i64`/`get_u64`: a `Value::Number`
+    /// that fails the conversion is out of range or not integral, not a
+    /// different JSON type, so `found` says why instead of repeating
+    /// "Number" (which `type_mismatch` would produce for both cases).
+    fn numeric_mismatch(key: &str, expected: &str, found: &Value) -> JsonifyError {
+        if let Value::Number(n) = found {
+            let reason = if n.is_f64() { "not integral" } else { "out of range" };
+            return JsonifyError::TypeMismatch {
+                key: key.to_string(),
+                expected: expected.to_string(),
+                found: format!("Number({}) {} for {}", n, reason, expected),
+            };
+        }
+        Self::type_mismatch(key, expected, found)
+    }
+}
+
+impl Jsonify {
+    fn leaf_kind(value: &Value) -> LeafKind {
+        match value {
+            Value::String(_) => LeafKind::Str,
+            Value::Bool(_) => LeafKind::Bool,
+            Value::Null => LeafKind::Null,
+            Value::Number(n) => {
+                if n.is_i64() || n.is_u64() {
+                    LeafKind::Int
+                } else {
+                    LeafKind::Float
+                }
+            }
+            _ => LeafKind::Unknown,
+        }
+    }
+
+    /// Unifies the leaf kinds observed at a single scalar field (e.g. every
+    /// element of an array) into one Rust type, wrapping it in `Option<_>`
+    /// if any observation was `null`.
+    fn unify_leaf_kinds(kinds: &[LeafKind]) -> FieldShape {
+        let mut nullable = false;
+        let mut base: Option<LeafKind> = None;
+
+        for &kind in kinds {
+            if kind == LeafKind::Null {
+                nullable = true;
+                continue;
+            }
+            base = Some(match base {
+                None => kind,
+                Some(LeafKind::Int) if kind == LeafKind::Float => LeafKind::Float,
+                Some(LeafKind::Float) if kind == LeafKind::Int => LeafKind::Float,
+                Some(existing) if existing == kind => existing,
+                Some(_) => LeafKind::Unknown,
+            });
+        }
+
+        let rust_type = match base {
+            Some(LeafKind::Str) => "String",
+            Some(LeafKind::Bool) => "bool",
+            Some(LeafKind::Int) => "i64",
+            Some(LeafKind::Float) => "f64",
+            Some(LeafKind::Unknown) | None => "serde_json::Value",
+            Some(LeafKind::Null) => unreachable!("Null is filtered out above"),
+        };
+
+        let scalar = FieldShape::Scalar(rust_type.to_string());
+        if nullable {
+            FieldShape::Nullable(Box::new(scalar))
+        } else {
+            scalar
+        }
+    }
+
+    /// Wraps `shape` in `Nullable` unless it already is one, so marking a
+    /// field optional twice over (e.g. an explicit `null` observation *and*
+    /// the field being absent from some sibling elements) doesn't nest it
+    /// in `Option<Option<_>>`.
+    fn make_optional(shape: FieldShape) -> FieldShape {
+        match shape {
+            FieldShape::Nullable(_) => shape,
+            other => FieldShape::Nullable(Box::new(other)),
+        }
+    }
+
+    /// Groups flattened `(path, leaf_kind, element_id)` entries into a tree
+    /// of structs/arrays/scalars, unifying the element type of arrays
+    /// across every observed index. `element_id` identifies which sibling
+    /// occurrence (document, or array element once an `any_index` level has
+    /// been crossed) an entry belongs to; a struct field missing from some
+    /// siblings' `element_id`s is wrapped in `Option<_>` even though it was
+    /// never observed as an explicit `null`. `path` is the dotted/bracket
+    /// path these `entries` live under, used only to name a `PathConflict`
+    /// if the same path is used inconsistently (e.g. an array mixing a
+    /// scalar element with an object element, or a key used as both a leaf
+    /// and a container) -- mirroring what `to_json` already rejects.
+    fn build_shape(
+        entries: Vec<(Vec<PathSegment>, LeafKind, usize)>,
+        path: &str,
+    ) -> Result<FieldShape, JsonifyError> {
+        if entries.iter().all(|(segments, _, _)| segments.is_empty()) {
+            let kinds: Vec<LeafKind> = entries.iter().map(|(_, kind, _)| *kind).collect();
+            return Ok(Self::unify_leaf_kinds(&kinds));
+        }
+        if entries.iter().any(|(segments, _, _)| segments.is_empty()) {
+            return Err(JsonifyError::PathConflict(path.to_string()));
+        }
+
+        let any_index = entries
+            .iter()
+            .any(|(segments, _, _)| matches!(segments.first(), Some(PathSegment::Index(_))));
+        let all_index = entries
+            .iter()
+            .all(|(segments, _, _)| matches!(segments.first(), Some(PathSegment::Index(_))));
+        if any_index != all_index {
+            return Err(JsonifyError::PathConflict(path.to_string()));
+        }
+
+        if any_index {
+            let rest: Vec<_> = entries
+                .into_iter()
+                .map(|(mut segments, kind, _)| match segments.remove(0) {
+                    PathSegment::Index(index) => (segments, kind, index),
+                    PathSegment::Key(_) => unreachable!("any_index == all_index guarantees Index"),
+                })
+                .collect();
+            let element_shape = Self::build_shape(rest, &format!("{}[]", path))?;
+            Ok(FieldShape::Array(Box::new(element_shape)))
+        } else {
+            let mut groups: BTreeMap<String, Vec<(Vec<PathSegment>, LeafKind, usize)>> = BTreeMap::new();
+            for (mut segments, kind, id) in entries {
+                if let PathSegment::Key(name) = segments.remove(0) {
+                    groups.entry(name).or_default().push((segments, kind, id));
+                }
+            }
+
+            let universe: BTreeSet<usize> = groups
+                .values()
+                .flatten()
+                .map(|(_, _, id)| *id)
+                .collect();
+
+            let mut fields = BTreeMap::new();
+            for (name, sub_entries) in groups {
+                let present: BTreeSet<usize> = sub_entries.iter().map(|(_, _, id)| *id).collect();
+                let partial = present.len() < universe.len();
+                let field_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", path, name)
+                };
+                let shape = Self::build_shape(sub_entries, &field_path)?;
+                fields.insert(name, if partial { Self::make_optional(shape) } else { shape });
+            }
+            Ok(FieldShape::Struct(fields))
+        }
+    }
+
+    fn capitalize(name: &str) -> String {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            None => String::new(),
+        }
+    }
+
+    /// Returns a struct name derived from `hint`, appending a counter
+    /// suffix (`Address`, `Address2`, ...) the second and later time the
+    /// same hint is used, so colliding nested object names stay distinct.
+    fn unique_struct_name(hint: &str, name_counts: &mut HashMap<String, u32>) -> String {
+        let count = name_counts.entry(hint.to_string()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            hint.to_string()
+        } else {
+            format!("{}{}", hint, count)
+        }
+    }
+
+    fn emit_shape(
+        shape: &FieldShape,
+        name_hint: &str,
+        name_counts: &mut HashMap<String, u32>,
+        structs: &mut Vec<String>,
+    ) -> String {
+        match shape {
+            FieldShape::Scalar(rust_type) => rust_type.clone(),
+            FieldShape::Nullable(inner) => {
+                let inner_type = Self::emit_shape(inner, name_hint, name_counts, structs);
+                format!("Option<{}>", inner_type)
+            }
+            FieldShape::Array(inner) => {
+                let inner_type = Self::emit_shape(inner, name_hint, name_counts, structs);
+                format!("Vec<{}>", inner_type)
+            }
+            FieldShape::Struct(fields) => {
+                let struct_name = Self::unique_struct_name(name_hint, name_counts);
+                let mut lines = vec![
+                    "#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]".to_string(),
+                    format!("pub struct {} {{", struct_name),
+                ];
+                for (field_name, field_shape) in fields {
+                    let field_type = Self::emit_shape(
+                        field_shape,
+                        &Self::capitalize(field_name),
+                        name_counts,
+                        structs,
+                    );
+                    lines.push(format!("    pub {}: {},", field_name, field_type));
+                }
+                lines.push("}".to_string());
+                structs.push(lines.join("\n"));
+                struct_name
+            }
+        }
+    }
+
+    /// Infers Rust `struct` definitions from the flattened keyspace and
+    /// renders them as serde-annotated source text, rooted at a struct
+    /// named `root_name`. Nested objects become their own struct (named
+    /// from their key segment, e.g. `address` -> `Address`), bracketed
+    /// paths become `Vec<T>` fields, a struct field missing from some
+    /// sibling array elements becomes `Option<T>`, and colliding generated
+    /// names are disambiguated with a numeric suffix. Returns
+    /// `JsonifyError::PathConflict` if the same path is used inconsistently
+    /// (e.g. an array mixing a scalar element with an object element), the
+    /// same condition `to_json` rejects.
+    pub fn to_rust_structs(&self, root_name: &str) -> Result<String, JsonifyError> {
+        let entries = self
+            .values
+            .iter()
+            .map(|(key, value)| (tokenize_path(key), Self::leaf_kind(&value.value), 0usize))
+            .collect();
+        let shape = Self::build_shape(entries, "")?;
+
+        let mut name_counts = HashMap::new();
+        let mut structs = Vec::new();
+        Self::emit_shape(&shape, root_name, &mut name_counts, &mut structs);
+        Ok(structs.join("\n\n"))
+    }
+}
+
+const JSONB_TAG_STRING: u8 = 0;
+const JSONB_TAG_NUMBER: u8 = 1;
+const JSONB_TAG_BOOL: u8 = 2;
+const JSONB_TAG_NULL: u8 = 3;
+
+/// Appends `value` to `buf` as an unsigned LEB128 varint.
+fn encode_varint(value: usize, buf: &mut Vec<u8>) {
+    let mut remaining = value as u64;
+    loop {
+        let mut byte = (remaining & 0x7f) as u8;
+        remaining >>= 7;
+        if remaining != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if remaining == 0 {
+            break;
+        }
+    }
+}
+
+/// Reads an unsigned LEB128 varint starting at `*pos`, advancing `*pos`
+/// past it.
+fn decode_varint(bytes: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Some(result as usize)
 }
 
+impl Jsonify {
+    /// Encodes the flattened entry set into a compact binary buffer: each
+    /// `(key, value_type, value)` triple is written as a varint-prefixed
+    /// key, a 1-byte type tag, and the encoded scalar. Decoded by
+    /// `from_jsonb` without re-parsing the document as JSON text; only each
+    /// number's stored textual form goes through `serde_json::from_str`.
+    pub fn to_jsonb(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut entries: Vec<&(String, JsonifyValue)> = self.values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (key, value) in entries {
+            let key_bytes = key.as_bytes();
+            encode_varint(key_bytes.len(), &mut buf);
+            buf.extend_from_slice(key_bytes);
+
+            match &value.value {
+                Value::String(s) => {
+                    buf.push(JSONB_TAG_STRING);
+                    let bytes = s.as_bytes();
+                    encode_varint(bytes.len(), &mut buf);
+                    buf.extend_from_slice(bytes);
+                }
+                Value::Number(n) => {
+                    buf.push(JSONB_TAG_NUMBER);
+                    let repr = n.to_string();
+                    let bytes = repr.as_bytes();
+                    encode_varint(bytes.len(), &mut buf);
+                    buf.extend_from_slice(bytes);
+                }
+                Value::Bool(b) => {
+                    buf.push(JSONB_TAG_BOOL);
+                    buf.push(*b as u8);
+                }
+                // Every flattened leaf is a scalar; anything else (object,
+                // array, or a genuinely unrepresentable value) is encoded
+                // as null rather than silently dropped.
+                _ => buf.push(JSONB_TAG_NULL),
+            }
+        }
+
+        buf
+    }
+
+    /// Decodes a buffer produced by `to_jsonb` directly into a `Jsonify`,
+    /// skipping the whole-document `serde_json::from_str` parse path; only
+    /// each number's stored text is parsed with `serde_json::from_str`.
+    pub fn from_jsonb(bytes: &[u8]) -> Self {
+        let mut values = HashSet::new();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            let Some(key_len) = decode_varint(bytes, &mut pos) else {
+                break;
+            };
+            if pos + key_len > bytes.len() {
+                break;
+            }
+            let key = String::from_utf8_lossy(&bytes[pos..pos + key_len]).into_owned();
+            pos += key_len;
+
+            let Some(&tag) = bytes.get(pos) else {
+                break;
+            };
+            pos += 1;
+
+            let decoded = match tag {
+                JSONB_TAG_STRING => decode_varint(bytes, &mut pos).and_then(|len| {
+                    if pos + len > bytes.len() {
+                        return None;
+                    }
+                    let s = String::from_utf8_lossy(&bytes[pos..pos + len]).into_owned();
+                    pos += len;
+                    Some((Value::String(s), "String".to_string()))
+                }),
+                JSONB_TAG_NUMBER => decode_varint(bytes, &mut pos).and_then(|len| {
+                    if pos + len > bytes.len() {
+                        return None;
+                    }
+                    let repr = String::from_utf8_lossy(&bytes[pos..pos + len]).into_owned();
+                    pos += len;
+                    let value = serde_json::from_str(&repr).unwrap_or(Value::Null);
+                    Some((value, "Number".to_string()))
+                }),
+                JSONB_TAG_BOOL => bytes.get(pos).map(|&b| {
+                    pos += 1;
+                    (Value::Bool(b != 0), "Bool".to_string())
+                }),
+                JSONB_TAG_NULL => Some((Value::Null, "Null".to_string())),
+                _ => None,
+            };
+
+            let Some((value, value_type)) = decoded else {
+                break;
+            };
+            values.insert((key, JsonifyValue { value, value_type }));
+        }
+
+        Jsonify {
+            values,
+            #[cfg(feature = "sled-store")]
+            store: None,
+        }
+    }
+}
+
+/// One leaf's byte-offset span within a `LazyJsonify`'s source buffer, plus
+/// its tracked type so callers can fail fast like `TypedAccess` does.
+#[derive(Debug, Clone)]
+struct LazyLeaf {
+    start: usize,
+    end: usize,
+    value_type: String,
+}
+
+/// A lazy, allocation-light counterpart to `Jsonify` for large documents.
+/// `new` parses the source once and records, for each leaf, the `(start,
+/// end)` byte-offset span of its value within the original buffer instead
+/// of cloning it; `get_value`/`to_json` slice and parse the original
+/// buffer on demand. Avoids the O(n^2)-ish re-serialization that `Jsonify`'s
+/// eager parser does by calling `value.to_string()` at every nesting level.
+pub struct LazyJsonify {
+    source: Arc<str>,
+    leaves: HashMap<String, LazyLeaf>,
+}
+
+impl LazyJsonify {
+    pub fn new(json: &str) -> Self {
+        let source: Arc<str> = Arc::from(json);
+        let bytes = source.as_bytes();
+        let mut leaves = HashMap::new();
+        let start = Self::skip_whitespace(bytes, 0);
+        Self::scan_value(bytes, start, "", &mut leaves);
+        LazyJsonify { source, leaves }
+    }
+
+    fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+        while i < bytes.len() && matches!(bytes[i], b' ' | b'\t' | b'\n' | b'\r') {
+            i += 1;
+        }
+        i
+    }
+
+    /// Scans the JSON string literal (including its surrounding quotes)
+    /// starting at `i`, returning the index just past the closing quote.
+    fn scan_string(bytes: &[u8], i: usize) -> usize {
+        let mut j = i + 1;
+        while j < bytes.len() {
+            match bytes[j] {
+                b'\\' => j += 2,
+                b'"' => return j + 1,
+                _ => j += 1,
+            }
+        }
+        j
+    }
+
+    fn scan_number(bytes: &[u8], i: usize) -> usize {
+        let mut j = i;
+        while j < bytes.len() && matches!(bytes[j], b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+            j += 1;
+        }
+        j
+    }
+
+    /// Scans a single JSON value starting at `i` (assumed past leading
+    /// whitespace), recording leaves under `prefix` and recursing into
+    /// objects/arrays. Returns the index just past the value.
+    fn scan_value(bytes: &[u8], i: usize, prefix: &str, leaves: &mut HashMap<String, LazyLeaf>) -> usize {
+        match bytes.get(i) {
+            Some(b'{') => Self::scan_object(bytes, i, prefix, leaves),
+            Some(b'[') => Self::scan_array(bytes, i, prefix, leaves),
+            Some(b'"') => {
+                let end = Self::scan_string(bytes, i);
+                leaves.insert(prefix.to_string(), LazyLeaf { start: i, end, value_type: "String".to_string() });
+                end
+            }
+            Some(b't') => {
+                let end = (i + "true".len()).min(bytes.len());
+                leaves.insert(prefix.to_string(), LazyLeaf { start: i, end, value_type: "Bool".to_string() });
+                end
+            }
+            Some(b'f') => {
+                let end = (i + "false".len()).min(bytes.len());
+                leaves.insert(prefix.to_string(), LazyLeaf { start: i, end, value_type: "Bool".to_string() });
+                end
+            }
+            Some(b'n') => {
+                let end = (i + "null".len()).min(bytes.len());
+                leaves.insert(prefix.to_string(), LazyLeaf { start: i, end, value_type: "Null".to_string() });
+                end
+            }
+            Some(_) => {
+                let end = Self::scan_number(bytes, i);
+                leaves.insert(prefix.to_string(), LazyLeaf { start: i, end, value_type: "Number".to_string() });
+                end
+            }
+            None => i,
+        }
+    }
+
+    fn scan_object(bytes: &[u8], i: usize, prefix: &str, leaves: &mut HashMap<String, LazyLeaf>) -> usize {
+        let mut j = Self::skip_whitespace(bytes, i + 1);
+        if bytes.get(j) == Some(&b'}') {
+            return j + 1;
+        }
+
+        loop {
+            j = Self::skip_whitespace(bytes, j);
+            let key_start = j;
+            let key_end = Self::scan_string(bytes, j);
+            let key: String = std::str::from_utf8(&bytes[key_start..key_end])
+                .ok()
+                .and_then(|s| serde_json::from_str(s).ok())
+                .unwrap_or_default();
+
+            j = Self::skip_whitespace(bytes, key_end);
+            j = Self::skip_whitespace(bytes, j + 1); // skip ':'
+
+            let escaped_key = escape_key_segment(&key);
+            let new_prefix = if prefix.is_empty() {
+                escaped_key
+            } else {
+                format!("{}.{}", prefix, escaped_key)
+            };
+            j = Self::scan_value(bytes, j, &new_prefix, leaves);
+            j = Self::skip_whitespace(bytes, j);
+
+            match bytes.get(j) {
+                Some(b',') => j = Self::skip_whitespace(bytes, j + 1),
+                Some(b'}') => return j + 1,
+                _ => return j,
+            }
+        }
+    }
+
+    fn scan_array(bytes: &[u8], i: usize, prefix: &str, leaves: &mut HashMap<String, LazyLeaf>) -> usize {
+        let mut j = Self::skip_whitespace(bytes, i + 1);
+        if bytes.get(j) == Some(&b']') {
+            return j + 1;
+        }
+
+        let mut index = 0usize;
+        loop {
+            j = Self::skip_whitespace(bytes, j);
+            let new_prefix = format!("{}[{}]", prefix, index);
+            j = Self::scan_value(bytes, j, &new_prefix, leaves);
+            index += 1;
+            j = Self::skip_whitespace(bytes, j);
+
+            match bytes.get(j) {
+                Some(b',') => j = Self::skip_whitespace(bytes, j + 1),
+                Some(b']') => return j + 1,
+                _ => return j,
+            }
+        }
+    }
+
+    /// Slices and parses the leaf stored at `key`, on demand.
+    pub fn get_value(&self, key: &str) -> Option<Value> {
+        let leaf = self.leaves.get(key)?;
+        serde_json::from_str(&self.source[leaf.start..leaf.end]).ok()
+    }
+
+    pub fn has_key(&self, key: &str) -> bool {
+        self.leaves.contains_key(key)
+    }
+
+    pub fn get_keys(&self) -> Vec<String> {
+        self.leaves.keys().cloned().collect()
+    }
+
+    /// Returns the tracked type (`"String"`, `"Number"`, `"Bool"` or
+    /// `"Null"`) of the leaf at `key` without slicing or parsing its value.
+    pub fn get_value_type(&self, key: &str) -> Option<&str> {
+        self.leaves.get(key).map(|leaf| leaf.value_type.as_str())
+    }
+
+    /// Rebuilds the nested document from the recorded leaf offsets,
+    /// slicing and parsing each one on demand, and serializes it back to a
+    /// JSON string.
+    pub fn to_json(&self) -> Result<String, JsonifyError> {
+        let mut root = Value::Null;
+
+        let mut keys: Vec<&String> = self.leaves.keys().collect();
+        keys.sort();
+
+        let leaf_paths: HashSet<&str> = keys.iter().map(|k| k.as_str()).collect();
+        for key in keys {
+            let leaf = &self.leaves[key];
+            let value: Value = serde_json::from_str(&self.source[leaf.start..leaf.end]).unwrap_or(Value::Null);
+            let segments = tokenize_path_with_offsets(key);
+            insert_path(&mut root, &segments, value, key, 0, &leaf_paths)?;
+        }
+
+        if root.is_null() {
+            root = Value::Object(serde_json::Map::new());
+        }
+
+        Ok(serde_json::to_string(&root).unwrap_or_else(|_| "{}".to_string()))
+    }
+}
+
+
+
 fn main() {
     let json_string = r#"{
         "name": "John Doe",
@@ -144,25 +1259,250 @@ fn main() {
         "address": {
             "city": "New York",
             "zip": "10001"
-        }
+        },
+        "cities": ["Paris", "Berlin"]
     }"#;
 
     let mut hashset = Jsonify::new(json_string);
 
-    println!("Initial JSON: {}", hashset.to_json());
+    println!("Initial JSON: {}", hashset.to_json().unwrap());
 
     hashset.remove_from_json("name");
-    println!("After removing 'name': {}", hashset.to_json());
+    println!("After removing 'name': {}", hashset.to_json().unwrap());
 
     hashset.add_to_json("country", Value::String("USA".to_string()));
-    println!("After adding 'country': {}", hashset.to_json());
+    println!("After adding 'country': {}", hashset.to_json().unwrap());
 
     println!("Does key 'age' exist? {}", hashset.has_key("age"));
 
     println!("All keys: {:?}", hashset.get_keys());
 
     hashset.merge_json(r#"{"state": "NY", "city": "Albany"}"#);
-    println!("After merging JSON: {}", hashset.to_json());
+    println!("After merging JSON: {}", hashset.to_json().unwrap());
+
+    hashset.merge_patch(r#"{"address": {"zip": null}, "age": 31}"#);
+    println!("After merge patch: {}", hashset.to_json().unwrap());
+
+    hashset.set_by_path("address.zip", Value::String("10002".to_string()));
+    println!("After set_by_path: {}", hashset.to_json().unwrap());
+
+    let address = hashset.get_subtree("address");
+    println!("Address subtree: {}", address.to_json().unwrap());
+
+    hashset.remove_by_path("address");
+    println!("After remove_by_path: {}", hashset.to_json().unwrap());
+
+    match hashset.get_str("city") {
+        Ok(city) => println!("city is '{}'", city),
+        Err(e) => println!("get_str error: {}", e),
+    }
+
+    match hashset.get_str("age") {
+        Ok(_) => println!("unexpected success"),
+        Err(e) => println!("get_str error: {}", e),
+    }
+
+    let typed = Jsonify::new(json_string);
+    println!("Generated structs:\n{}", typed.to_rust_structs("Person").unwrap());
+
+    #[cfg(feature = "sled-store")]
+    {
+        let dir = std::env::temp_dir().join("jsonify-demo-db");
+        let mut persisted = Jsonify::open(&dir).expect("open sled store");
+        persisted.add_to_json("ping", Value::String("pong".to_string()));
+        drop(persisted);
+
+        let reopened = Jsonify::open(&dir).expect("reopen sled store");
+        println!("Reopened store: {}", reopened.to_json().unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    let lazy = LazyJsonify::new(json_string);
+    println!("Lazy get_value('address.city'): {:?}", lazy.get_value("address.city"));
+    println!("Lazy to_json: {}", lazy.to_json().unwrap());
+
+    let original = Jsonify::new(json_string);
+    let encoded = original.to_jsonb();
+    let decoded = Jsonify::from_jsonb(&encoded);
+    println!("Round-tripped through jsonb ({} bytes): {}", encoded.len(), decoded.to_json().unwrap());
+}
 
-   
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json_reconstructs_nested_objects_and_arrays() {
+        let doc = Jsonify::new(r#"{"a":{"b":1},"c":[1,2,3]}"#);
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"a":{"b":1},"c":[1,2,3]}));
+    }
+
+    #[test]
+    fn to_json_null_pads_sparse_arrays() {
+        let mut doc = Jsonify::new("{}");
+        doc.add_to_json("items[2]", Value::String("x".into()));
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"items": [null, null, "x"]}));
+    }
+
+    #[test]
+    fn to_json_reports_path_conflict() {
+        let mut doc = Jsonify::new(r#"{"a":1}"#);
+        doc.add_to_json("a.b", Value::String("x".into()));
+        assert_eq!(doc.to_json(), Err(JsonifyError::PathConflict("a.b".to_string())));
+    }
+
+    #[test]
+    fn to_json_reports_path_conflict_through_an_explicit_null_leaf() {
+        let mut doc = Jsonify::new(r#"{"a": null}"#);
+        doc.add_to_json("a.b", Value::from(1));
+        assert_eq!(doc.to_json(), Err(JsonifyError::PathConflict("a.b".to_string())));
+    }
+
+    #[test]
+    fn to_json_round_trips_keys_containing_literal_dots_and_brackets() {
+        let doc = Jsonify::new(r#"{"a.b": 1, "c[0]": 2}"#);
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"a.b": 1, "c[0]": 2}));
+    }
+
+    #[test]
+    fn merge_patch_null_deletes_key_and_descendants() {
+        let mut doc = Jsonify::new(r#"{"address":{"city":"NY","zip":"10001"}}"#);
+        doc.merge_patch(r#"{"address":{"zip":null}}"#);
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"address":{"city":"NY"}}));
+    }
+
+    #[test]
+    fn merge_patch_object_clears_stale_scalar_leaf() {
+        let mut doc = Jsonify::new(r#"{"address":"unknown"}"#);
+        doc.merge_patch(r#"{"address":{"city":"NY"}}"#);
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"address":{"city":"NY"}}));
+    }
+
+    #[test]
+    fn merge_patch_non_object_replaces_whole_document() {
+        let mut doc = Jsonify::new(r#"{"a":1,"b":2}"#);
+        doc.merge_patch("5");
+        let value: Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!(5));
+    }
+
+    #[test]
+    fn path_accessors_set_get_remove() {
+        let mut doc = Jsonify::new(r#"{"a":{"b":1}}"#);
+        assert_eq!(doc.get_by_path("a.b"), Some(Value::from(1)));
+
+        doc.set_by_path("a.c", serde_json::json!({"d": 2}));
+        assert_eq!(doc.get_by_path("a.c.d"), Some(Value::from(2)));
+
+        assert!(doc.remove_by_path("a.b"));
+        assert!(!doc.has_key("a.b"));
+        assert!(!doc.remove_by_path("a.b"));
+    }
+
+    #[test]
+    fn get_subtree_rebases_keys_under_prefix() {
+        let doc = Jsonify::new(r#"{"address":{"city":"NY","cities":["a","b"]}}"#);
+        let subtree = doc.get_subtree("address");
+        assert_eq!(subtree.get_by_path("city"), Some(Value::String("NY".to_string())));
+        assert_eq!(subtree.get_by_path("cities[0]"), Some(Value::String("a".to_string())));
+    }
+
+    #[test]
+    fn typed_accessors_return_values_and_errors() {
+        let doc = Jsonify::new(r#"{"name":"Ann","age":30}"#);
+        assert_eq!(doc.get_str("name").unwrap(), "Ann");
+        assert_eq!(doc.get_i64("age").unwrap(), 30);
+        assert_eq!(doc.get_str("missing"), Err(JsonifyError::KeyNotFound("missing".to_string())));
+        assert_eq!(
+            doc.get_str("age"),
+            Err(JsonifyError::TypeMismatch {
+                key: "age".to_string(),
+                expected: "String".to_string(),
+                found: "Number".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn get_u64_on_negative_number_reports_the_value_out_of_range() {
+        let doc = Jsonify::new(r#"{"age":-5}"#);
+        let err = doc.get_u64("age").unwrap_err();
+        match err {
+            JsonifyError::TypeMismatch { expected, found, .. } => {
+                assert_eq!(expected, "u64");
+                assert!(found.contains("-5"), "found = {found}");
+            }
+            other => panic!("expected TypeMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn to_rust_structs_infers_fields_and_nested_struct() {
+        let doc = Jsonify::new(r#"{"name":"Ann","age":30,"address":{"city":"NY"}}"#);
+        let generated = doc.to_rust_structs("Person").unwrap();
+        assert!(generated.contains("pub struct Person {"));
+        assert!(generated.contains("pub struct Address {"));
+        assert!(generated.contains("pub age: i64,"));
+        assert!(generated.contains("pub address: Address,"));
+    }
+
+    #[test]
+    fn to_rust_structs_makes_field_optional_when_absent_from_some_array_elements() {
+        let doc = Jsonify::new(r#"{"items":[{"a":1,"b":2},{"a":3}]}"#);
+        let generated = doc.to_rust_structs("Root").unwrap();
+        assert!(generated.contains("pub a: i64,"));
+        assert!(generated.contains("pub b: Option<i64>,"));
+    }
+
+    #[test]
+    fn to_rust_structs_reports_path_conflict_for_inconsistent_array_elements() {
+        let doc = Jsonify::new(r#"{"items":[{"a":1},"str"]}"#);
+        assert!(matches!(doc.to_rust_structs("Root"), Err(JsonifyError::PathConflict(_))));
+    }
+
+    #[test]
+    fn jsonb_round_trips_through_to_json() {
+        let doc = Jsonify::new(r#"{"a":1,"b":"x","c":true,"d":null}"#);
+        let bytes = doc.to_jsonb();
+        let decoded = Jsonify::from_jsonb(&bytes);
+        assert_eq!(decoded.to_json().unwrap(), doc.to_json().unwrap());
+    }
+
+    #[test]
+    fn lazy_jsonify_matches_eager_to_json() {
+        let json = r#"{"a":{"b":1},"c":[1,2,3],"d":"x"}"#;
+        let eager = Jsonify::new(json);
+        let lazy = LazyJsonify::new(json);
+        assert_eq!(eager.to_json().unwrap(), lazy.to_json().unwrap());
+        assert_eq!(lazy.get_value("a.b"), Some(Value::from(1)));
+    }
+
+    #[test]
+    fn lazy_jsonify_does_not_panic_on_truncated_literal() {
+        let lazy = LazyJsonify::new("t");
+        assert_eq!(lazy.get_value(""), None);
+    }
+
+    #[cfg(feature = "sled-store")]
+    #[test]
+    fn sled_store_persists_writes_across_reopen() {
+        let dir = std::env::temp_dir().join(format!("jsonify-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut doc = Jsonify::open(&dir).unwrap();
+        doc.add_to_json("name", Value::String("Ann".to_string()));
+        doc.set_by_path("address.city", Value::String("NY".to_string()));
+        drop(doc);
+
+        let reopened = Jsonify::open(&dir).unwrap();
+        assert_eq!(reopened.get_by_path("name"), Some(Value::String("Ann".to_string())));
+        assert_eq!(reopened.get_by_path("address.city"), Some(Value::String("NY".to_string())));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }